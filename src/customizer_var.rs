@@ -0,0 +1,136 @@
+/**
+    A `[min:max]` or `[min:step:max]` slider range for a `CustomizerVar`, shown
+    as an OpenSCAD Customizer comment after the variable's value.
+*/
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct CustomizerRange {
+    min: f64,
+    step: Option<f64>,
+    max: f64,
+}
+
+impl CustomizerRange {
+    pub fn new(min: f64, max: f64) -> CustomizerRange {
+        CustomizerRange {
+            min,
+            step: None,
+            max,
+        }
+    }
+
+    pub fn with_step(min: f64, step: f64, max: f64) -> CustomizerRange {
+        CustomizerRange {
+            min,
+            step: Some(step),
+            max,
+        }
+    }
+
+    fn get_code(&self) -> String {
+        match self.step {
+            Some(step) => format!("[{}:{}:{}]", self.min, step, self.max),
+            None => format!("[{}:{}]", self.min, self.max),
+        }
+    }
+}
+
+/**
+    A Customizer-compatible variable declaration for the top of a `ScadFile`.
+
+    Renders as a plain `name = default;` assignment followed by an OpenSCAD
+    Customizer comment that turns it into a slider (`with_range`) or a
+    dropdown (`with_choices`) in the OpenSCAD GUI. `with_section` groups the
+    variable under a `/* [Section] */` header shared with neighbouring vars
+    in the same section.
+
+    ```SCAD
+    /* [Size] */
+    width = 10; // [0:1:100]
+    ```
+*/
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct CustomizerVar {
+    name: String,
+    default: String,
+    range: Option<CustomizerRange>,
+    choices: Option<Vec<String>>,
+    section: Option<String>,
+}
+
+impl CustomizerVar {
+    pub fn new(name: &str, default: &str) -> CustomizerVar {
+        CustomizerVar {
+            name: String::from(name),
+            default: String::from(default),
+            range: None,
+            choices: None,
+            section: None,
+        }
+    }
+
+    pub fn with_range(mut self, range: CustomizerRange) -> CustomizerVar {
+        self.range = Some(range);
+        self
+    }
+
+    pub fn with_choices(mut self, choices: Vec<String>) -> CustomizerVar {
+        self.choices = Some(choices);
+        self
+    }
+
+    pub fn with_section(mut self, section: &str) -> CustomizerVar {
+        self.section = Some(String::from(section));
+        self
+    }
+
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+
+    /**
+      Returns the scad code for the variable declaration, including its
+      trailing Customizer comment if a range or choice list was given.
+    */
+    pub fn get_code(&self) -> String {
+        let mut code = format!("{} = {};", self.name, self.default);
+
+        if let Some(range) = &self.range {
+            code = code + " // " + &range.get_code();
+        } else if let Some(choices) = &self.choices {
+            code = code + " // [" + &choices.join(", ") + "]";
+        }
+
+        code
+    }
+}
+
+#[cfg(test)]
+mod customizer_var_tests {
+    use super::*;
+
+    #[test]
+    fn range_test() {
+        let var =
+            CustomizerVar::new("width", "10").with_range(CustomizerRange::with_step(0., 1., 100.));
+
+        assert_eq!(var.get_code(), "width = 10; // [0:1:100]");
+    }
+
+    #[test]
+    fn choices_test() {
+        let var = CustomizerVar::new("shape", "\"circle\"")
+            .with_choices(vec![String::from("\"circle\""), String::from("\"square\"")]);
+
+        assert_eq!(
+            var.get_code(),
+            "shape = \"circle\"; // [\"circle\", \"square\"]"
+        );
+    }
+
+    #[test]
+    fn plain_test() {
+        let var = CustomizerVar::new("enabled", "true");
+
+        assert_eq!(var.get_code(), "enabled = true;");
+    }
+}
@@ -0,0 +1,22 @@
+pub use nalgebra as na;
+
+mod customizer_var;
+mod parser;
+mod scad_element;
+mod scad_file;
+mod scad_function;
+mod scad_module;
+mod scad_object;
+
+pub use crate::customizer_var::{CustomizerRange, CustomizerVar};
+pub use crate::parser::{parse_file, parse_object, ParseError};
+pub use crate::scad_element::ScadElement;
+pub use crate::scad_file::ScadFile;
+pub use crate::scad_function::ScadFunction;
+pub use crate::scad_module::{ScadModule, ScadParameter};
+pub use crate::scad_object::ScadObject;
+
+/// Convenience constructor for a `na::Vector3<f64>`, used all over the scad API.
+pub fn vec3(x: f64, y: f64, z: f64) -> na::Vector3<f64> {
+    na::Vector3::new(x, y, z)
+}
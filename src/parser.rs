@@ -0,0 +1,349 @@
+use crate::{na, ScadElement, ScadFile, ScadObject};
+
+/**
+    Error returned by `parse_object`/`parse_file`, the inverse of
+    `ScadObject::get_code`/`ScadFile::get_code`, which parse scad source text
+    back into the corresponding object tree.
+
+    They make up a subset parser: it understands nested `{ }` child blocks,
+    trailing `;` leaf statements, the `!`/`#`/`%`/`*` modifier prefixes and
+    `$name=value;` special variable assignments (both file-wide, via
+    `ScadFile::set_special`, and scoped to an object via
+    `ScadObject::with_special`), which is enough to round-trip anything
+    built out of the primitives this crate knows how to emit (see
+    `ScadElement`). Calls to unrecognised modules/functions parse as
+    `ScadElement::Call`.
+*/
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError(String);
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a single scad statement (e.g. `"cube([1,1,1]);"`) into an `ScadObject`.
+pub fn parse_object(source: &str) -> Result<ScadObject, ParseError> {
+    let (obj, rest) = parse_statement(source.trim_start())?;
+
+    if !rest.trim().is_empty() {
+        return Err(ParseError(format!("unexpected trailing input: {:?}", rest)));
+    }
+
+    Ok(obj)
+}
+
+/// Parses a whole scad file's worth of `$name=value;` globals and top-level objects.
+pub fn parse_file(source: &str) -> Result<ScadFile, ParseError> {
+    let mut sfile = ScadFile::new();
+    let mut remaining = source.trim_start();
+
+    while !remaining.is_empty() {
+        if remaining.starts_with('$') {
+            let (name, value, rest) = parse_special(remaining)?;
+            sfile.set_special(&name, &value);
+            remaining = rest.trim_start();
+        } else {
+            let (obj, rest) = parse_statement(remaining)?;
+            sfile.add_object(obj);
+            remaining = rest.trim_start();
+        }
+    }
+
+    Ok(sfile)
+}
+
+fn parse_statement(input: &str) -> Result<(ScadObject, &str), ParseError> {
+    let mut rest = input.trim_start();
+
+    let mut important = false;
+    let mut highlight = false;
+    let mut transparent = false;
+    let mut disable = false;
+
+    loop {
+        match rest.chars().next() {
+            Some('!') => important = true,
+            Some('#') => highlight = true,
+            Some('%') => transparent = true,
+            Some('*') => disable = true,
+            _ => break,
+        }
+        rest = rest[1..].trim_start();
+    }
+
+    let (name, after_name) = parse_identifier(rest)?;
+    let (args, after_args) = parse_balanced(after_name.trim_start(), '(', ')')?;
+
+    let element = element_from_call(&name, &args);
+
+    let (mut obj, rest) = match after_args.trim_start().chars().next() {
+        Some(';') => (ScadObject::new(element), &after_args.trim_start()[1..]),
+        Some('{') => {
+            let (body, after_body) = parse_balanced(after_args.trim_start(), '{', '}')?;
+            let mut obj = ScadObject::new(element);
+
+            let mut body_rest = body.trim_start();
+            while !body_rest.is_empty() {
+                if body_rest.starts_with('$') {
+                    let (name, value, next) = parse_special(body_rest)?;
+                    obj = obj.with_special(name, value);
+                    body_rest = next.trim_start();
+                } else {
+                    let (child, next) = parse_statement(body_rest)?;
+                    obj.add_child(child);
+                    body_rest = next.trim_start();
+                }
+            }
+
+            (obj, after_body)
+        }
+        _ => {
+            return Err(ParseError(format!(
+                "expected ';' or '{{' after {}(...)",
+                name
+            )))
+        }
+    };
+
+    // `ScadObject` can only represent one of !/#/%/* at a time (each setter
+    // clears the others), so a source statement combining more than one is
+    // ambiguous and must be rejected rather than silently dropping modifiers.
+    let modifier_count = [important, highlight, transparent, disable]
+        .iter()
+        .filter(|set| **set)
+        .count();
+    if modifier_count > 1 {
+        return Err(ParseError(format!(
+            "statement combines more than one of '!'/'#'/'%'/'*', which ScadObject cannot represent: {:?}",
+            input
+        )));
+    }
+
+    if important {
+        obj = obj.important();
+    } else if highlight {
+        obj = obj.highlight();
+    } else if transparent {
+        obj = obj.transparent();
+    } else if disable {
+        obj = obj.disable();
+    }
+
+    Ok((obj, rest))
+}
+
+fn element_from_call(name: &str, args: &str) -> ScadElement {
+    match name {
+        "union" => ScadElement::Union,
+        "difference" => ScadElement::Difference,
+        "translate" => parse_vector3(args)
+            .map(ScadElement::Translate)
+            .unwrap_or_else(|_| call(name, args)),
+        "cube" => parse_vector3(args)
+            .map(ScadElement::Cube)
+            .unwrap_or_else(|_| call(name, args)),
+        _ => call(name, args),
+    }
+}
+
+fn call(name: &str, args: &str) -> ScadElement {
+    ScadElement::Call {
+        name: String::from(name),
+        args: split_top_level(args),
+    }
+}
+
+fn parse_vector3(args: &str) -> Result<na::Vector3<f64>, ParseError> {
+    let inner = args
+        .trim()
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| ParseError(format!("expected a [x,y,z] vector, found {:?}", args)))?;
+
+    let components = split_top_level(inner);
+    if components.len() != 3 {
+        return Err(ParseError(format!(
+            "expected 3 vector components, found {}",
+            components.len()
+        )));
+    }
+
+    let parse_component = |s: &str| {
+        s.parse::<f64>()
+            .map_err(|_| ParseError(format!("invalid number {:?}", s)))
+    };
+
+    Ok(na::Vector3::new(
+        parse_component(&components[0])?,
+        parse_component(&components[1])?,
+        parse_component(&components[2])?,
+    ))
+}
+
+fn split_top_level(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(input[start..i].trim().to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(input[start..].trim().to_string());
+
+    args
+}
+
+fn parse_balanced(input: &str, open: char, close: char) -> Result<(String, &str), ParseError> {
+    let mut chars = input.char_indices();
+
+    match chars.next() {
+        Some((_, c)) if c == open => {}
+        _ => {
+            return Err(ParseError(format!(
+                "expected '{}', found {:?}",
+                open, input
+            )))
+        }
+    }
+
+    let mut depth = 1;
+    for (i, c) in chars {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Ok((input[1..i].to_string(), &input[i + 1..]));
+            }
+        }
+    }
+
+    Err(ParseError(format!("unterminated '{}'", open)))
+}
+
+fn parse_identifier(input: &str) -> Result<(String, &str), ParseError> {
+    let end = input
+        .char_indices()
+        .find(|(_, c)| !(c.is_alphanumeric() || *c == '_'))
+        .map(|(i, _)| i)
+        .unwrap_or(input.len());
+
+    if end == 0 {
+        return Err(ParseError(format!(
+            "expected an identifier, found {:?}",
+            input
+        )));
+    }
+
+    Ok((input[..end].to_string(), &input[end..]))
+}
+
+/// Parses a `$name=value;` special variable assignment, returning the name,
+/// the raw (unparsed) value text and the input following the `;`.
+fn parse_special(input: &str) -> Result<(String, String, &str), ParseError> {
+    let after_dollar = expect_char(input, '$')?;
+    let (name, after_name) = parse_identifier(after_dollar)?;
+    let after_eq = expect_char(after_name.trim_start(), '=')?;
+
+    let end = after_eq.find(';').ok_or_else(|| {
+        ParseError(format!(
+            "unterminated special variable assignment: {:?}",
+            input
+        ))
+    })?;
+
+    let value = after_eq[..end].trim().to_string();
+    Ok((name, value, &after_eq[end + 1..]))
+}
+
+fn expect_char(input: &str, expected: char) -> Result<&str, ParseError> {
+    match input.chars().next() {
+        Some(c) if c == expected => Ok(&input[expected.len_utf8()..]),
+        _ => Err(ParseError(format!(
+            "expected '{}', found {:?}",
+            expected, input
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod parser_tests {
+    use super::*;
+    use crate::ScadObject;
+
+    #[test]
+    fn round_trip_test() {
+        let mut obj = ScadObject::new(ScadElement::Translate(na::Vector3::new(1.0, 2.0, 3.0)));
+        obj.add_child(ScadObject::new(ScadElement::Cube(na::Vector3::new(
+            1.0, 1.0, 1.0,
+        ))));
+
+        assert_eq!(parse_object(&obj.get_code()).unwrap(), obj);
+    }
+
+    #[test]
+    fn modifier_test() {
+        let obj = ScadObject::new(ScadElement::Union).important();
+
+        assert_eq!(parse_object(&obj.get_code()).unwrap(), obj);
+    }
+
+    #[test]
+    fn multiple_modifiers_test() {
+        assert!(parse_object("*!union();").is_err());
+        assert!(parse_object("#!union();").is_err());
+        assert!(parse_object("!#union();").is_err());
+    }
+
+    #[test]
+    fn call_test() {
+        let obj = ScadObject::new(ScadElement::Call {
+            name: String::from("foo"),
+            args: vec![String::from("1"), String::from("2")],
+        });
+
+        assert_eq!(parse_object(&obj.get_code()).unwrap(), obj);
+    }
+
+    #[test]
+    fn file_test() {
+        let mut sfile = ScadFile::new();
+        sfile.set_detail(30);
+        sfile.add_object(ScadObject::new(ScadElement::Union));
+        sfile.add_object(ScadObject::new(ScadElement::Difference));
+
+        assert_eq!(parse_file(&sfile.get_code()).unwrap(), sfile);
+    }
+
+    #[test]
+    fn file_special_vars_test() {
+        let mut sfile = ScadFile::new();
+        sfile.set_special("fa", "5");
+        sfile.set_detail(30);
+
+        assert_eq!(parse_file(&sfile.get_code()).unwrap(), sfile);
+    }
+
+    #[test]
+    fn with_special_test() {
+        let obj = ScadObject::new(ScadElement::Union).with_special("fn", "50");
+
+        assert_eq!(parse_object(&obj.get_code()).unwrap(), obj);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::na;
+
+/**
+    The different kinds of scad statements that an `ScadObject` can wrap.
+
+    Each variant knows how to render itself to the bit of scad source that
+    goes before the `;` or `{ ... }` of the enclosing `ScadObject`.
+*/
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub enum ScadElement {
+    Union,
+
+    Difference,
+
+    Translate(na::Vector3<f64>),
+
+    Cube(na::Vector3<f64>),
+
+    /**
+      Instantiates a module or function defined elsewhere in the file (see
+      `ScadModule`/`ScadFunction`), passing `args` through verbatim and in order.
+    */
+    Call {
+        name: String,
+        args: Vec<String>,
+    },
+}
+
+impl ScadElement {
+    /**
+      Returns the scad code for the element, not including any children or
+      the trailing `;`/`{}` block that `ScadObject` adds.
+    */
+    pub fn get_code(self) -> String {
+        match self {
+            ScadElement::Union => String::from("union()"),
+            ScadElement::Difference => String::from("difference()"),
+            ScadElement::Translate(vec) => format!("translate([{},{},{}])", vec.x, vec.y, vec.z),
+            ScadElement::Cube(vec) => format!("cube([{},{},{}])", vec.x, vec.y, vec.z),
+            ScadElement::Call { name, args } => format!("{}({})", name, args.join(",")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod element_tests {
+    use super::*;
+
+    #[test]
+    fn call_test() {
+        let call = ScadElement::Call {
+            name: String::from("foo"),
+            args: vec![String::from("1"), String::from("2")],
+        };
+
+        assert_eq!(call.get_code(), "foo(1,2)");
+    }
+}
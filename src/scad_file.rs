@@ -1,5 +1,7 @@
-use crate::ScadObject;
-use std::{fs, io, path::Path};
+use crate::{CustomizerVar, ScadFunction, ScadModule, ScadObject};
+use std::collections::BTreeMap;
+use std::io::Write as IoWrite;
+use std::{fmt, fs, io, path::Path};
 
 /**
     Object that stores scad objects along with global parameters for
@@ -8,46 +10,160 @@ use std::{fs, io, path::Path};
 #[derive(Clone, PartialEq, PartialOrd, Debug)]
 pub struct ScadFile {
     objects: Vec<ScadObject>,
-    detail: i32,
+    modules: Vec<ScadModule>,
+    functions: Vec<ScadFunction>,
+    customizer_vars: Vec<CustomizerVar>,
+    imports: Vec<ScadImport>,
+    specials: BTreeMap<String, String>,
+}
+
+/// A `use <path>` (modules/functions only) or `include <path>` (textual) directive.
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+enum ScadImport {
+    Use(String),
+    Include(String),
+}
+
+impl ScadImport {
+    fn get_code(&self) -> String {
+        match self {
+            ScadImport::Use(path) => format!("use <{}>;", path),
+            ScadImport::Include(path) => format!("include <{}>;", path),
+        }
+    }
 }
 
 impl ScadFile {
     pub fn new() -> ScadFile {
         ScadFile {
             objects: Vec::new(),
-
-            detail: 0,
+            modules: Vec::new(),
+            functions: Vec::new(),
+            customizer_vars: Vec::new(),
+            imports: Vec::new(),
+            specials: BTreeMap::new(),
         }
     }
 
     /**
-        Returns the code for the global parameters as well as all the
-        children in the file
+        Returns the code for the Customizer variables, the imports, the global
+        special variables, the module/function definitions and all the
+        objects in the file, in that order. Thin wrapper around `write_code`
+        for callers that just want a `String`.
     */
     pub fn get_code(&self) -> String {
-        let mut result = String::from("");
+        let mut result = String::new();
+        // Writing to a String can never fail.
+        self.write_code(&mut result)
+            .expect("formatting never fails");
+        result
+    }
+
+    /**
+        Writes the code for the Customizer variables, the imports, the global
+        special variables, the module/function definitions and all the
+        objects in the file into `out`, in that order.
+    */
+    pub fn write_code<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        let mut last_section: Option<&str> = None;
+        for var in &self.customizer_vars {
+            if var.section() != last_section {
+                if let Some(section) = var.section() {
+                    writeln!(out, "/* [{}] */", section)?;
+                }
+                last_section = var.section();
+            }
+            writeln!(out, "{}", var.get_code())?;
+        }
+
+        for import in &self.imports {
+            writeln!(out, "{}", import.get_code())?;
+        }
+
+        for (name, value) in &self.specials {
+            writeln!(out, "${}={};", name, value)?;
+        }
+
+        for module in &self.modules {
+            writeln!(out, "{}", module.get_code())?;
+        }
 
-        if self.detail != 0 {
-            result = result + "$fn=" + &self.detail.to_string() + ";\n";
+        for function in &self.functions {
+            writeln!(out, "{}", function.get_code())?;
         }
 
         for object in &self.objects {
-            result = result + &object.get_code() + "\n";
+            object.write_code(out, 0)?;
+            out.write_char('\n')?;
         }
 
-        result
+        Ok(())
     }
 
     pub fn add_object(&mut self, object: ScadObject) {
         self.objects.push(object);
     }
 
+    /**
+     Adds a Customizer variable declaration, emitted at the very top of
+     `get_code()` (grouped by `CustomizerVar::with_section`, in insertion order).
+    */
+    pub fn add_customizer_var(&mut self, var: CustomizerVar) {
+        self.customizer_vars.push(var);
+    }
+
+    /**
+     Adds a `use <path>;` directive, importing the modules/functions defined
+     in the external scad file at `path` without including its top-level
+     geometry. Emitted before the $fn/object output in `get_code()`, in the
+     order added relative to `add_include`.
+    */
+    pub fn add_use(&mut self, path: &str) {
+        self.imports.push(ScadImport::Use(String::from(path)));
+    }
+
+    /**
+     Adds an `include <path>;` directive, textually including the external
+     scad file at `path` (its top-level geometry is rendered too). Emitted
+     before the $fn/object output in `get_code()`, in the order added
+     relative to `add_use`.
+    */
+    pub fn add_include(&mut self, path: &str) {
+        self.imports.push(ScadImport::Include(String::from(path)));
+    }
+
+    /**
+     Adds a module definition, emitted before the objects in `get_code()`.
+     The module can then be instantiated anywhere via `ScadElement::Call`.
+    */
+    pub fn add_module(&mut self, module: ScadModule) {
+        self.modules.push(module);
+    }
+
+    /**
+     Adds a function definition, emitted before the objects in `get_code()`.
+    */
+    pub fn add_function(&mut self, function: ScadFunction) {
+        self.functions.push(function);
+    }
+
     /**
      Sets the $fn variable for the whole file. This varibale defines  the detail
-     amount for cylindrical objects
+     amount for cylindrical objects. Shorthand for `set_special("fn", ...)`.
     */
     pub fn set_detail(&mut self, detail: i32) {
-        self.detail = detail;
+        self.set_special("fn", &detail.to_string());
+    }
+
+    /**
+     Sets a special variable (e.g. `"fa"`, `"fs"`, `"fn"`, `"t"`, `"vpr"`,
+     `"vpt"`, `"vpd"`) for the whole file. Emitted as `$name=value;` lines in
+     `get_code()`, in a stable (alphabetical) order. Can be overridden for a
+     single subtree with `ScadObject::with_special`.
+    */
+    pub fn set_special(&mut self, name: &str, value: &str) {
+        self.specials
+            .insert(String::from(name), String::from(value));
     }
 
     /**
@@ -63,7 +179,15 @@ impl ScadFile {
      writing fails.
     */
     pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        fs::write(&path, self.get_code().as_bytes())
+        let mut writer = io::BufWriter::new(fs::File::create(path)?);
+        let mut adapter = IoWriteAdapter::new(&mut writer);
+        if self.write_code(&mut adapter).is_err() {
+            // write_code only fails if the adapter's write_str did, so this is populated.
+            return Err(adapter
+                .into_error()
+                .expect("write_code error implies a captured io::Error"));
+        }
+        writer.flush()
     }
 }
 
@@ -73,6 +197,34 @@ impl Default for ScadFile {
     }
 }
 
+/// Adapts an `io::Write` so `write_code`'s `fmt::Write` bound can stream
+/// straight into a file instead of building one giant intermediate `String`.
+/// `fmt::Write` can't carry an `io::Error` through its `Err` variant, so the
+/// real error is stashed here and recovered by the caller after the fact.
+struct IoWriteAdapter<'a, W: IoWrite> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: IoWrite> IoWriteAdapter<'a, W> {
+    fn new(inner: &'a mut W) -> IoWriteAdapter<'a, W> {
+        IoWriteAdapter { inner, error: None }
+    }
+
+    fn into_error(self) -> Option<io::Error> {
+        self.error
+    }
+}
+
+impl<'a, W: IoWrite> fmt::Write for IoWriteAdapter<'a, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
 #[cfg(test)]
 mod file_tests {
     use super::*;
@@ -82,7 +234,7 @@ mod file_tests {
     fn detail_test() {
         let mut sfile = ScadFile::new();
 
-        sfile.detail = 30;
+        sfile.set_detail(30);
 
         assert_eq!(sfile.get_code(), "$fn=30;\n");
 
@@ -101,11 +253,56 @@ mod file_tests {
         let file_path = out_dir.path().join("test.scad");
         let mut sfile = ScadFile::new();
 
-        sfile.detail = 30;
+        sfile.set_detail(30);
 
         sfile.write_to_file(&file_path).unwrap();
 
         let file_content = fs::read_to_string(&file_path).unwrap();
         assert_eq!(file_content, sfile.get_code());
     }
+
+    #[test]
+    fn special_vars_test() {
+        let mut sfile = ScadFile::new();
+
+        sfile.set_special("fs", "2");
+        sfile.set_special("fa", "5");
+        sfile.set_detail(30);
+
+        assert_eq!(sfile.get_code(), "$fa=5;\n$fn=30;\n$fs=2;\n");
+    }
+
+    #[test]
+    fn customizer_vars_test() {
+        use crate::{CustomizerRange, CustomizerVar};
+
+        let mut sfile = ScadFile::new();
+
+        sfile.add_customizer_var(
+            CustomizerVar::new("width", "10")
+                .with_range(CustomizerRange::with_step(0., 1., 100.))
+                .with_section("Size"),
+        );
+        sfile.add_customizer_var(CustomizerVar::new("height", "5").with_section("Size"));
+        sfile.add_customizer_var(CustomizerVar::new("enabled", "true"));
+
+        assert_eq!(
+            sfile.get_code(),
+            "/* [Size] */\nwidth = 10; // [0:1:100]\nheight = 5;\nenabled = true;\n"
+        );
+    }
+
+    #[test]
+    fn imports_test() {
+        let mut sfile = ScadFile::new();
+
+        sfile.add_use("BOSL2/std.scad");
+        sfile.add_include("MCAD/shapes.scad");
+        sfile.set_detail(30);
+
+        assert_eq!(
+            sfile.get_code(),
+            "use <BOSL2/std.scad>;\ninclude <MCAD/shapes.scad>;\n$fn=30;\n"
+        );
+    }
 }
@@ -0,0 +1,65 @@
+use crate::scad_module::ScadParameter;
+
+/**
+    A reusable scad `function`, made up of a name, a parameter list and an
+    expression body.
+
+    ```SCAD
+    function foo(w, h) = w * h;
+    ```
+
+    Unlike `ScadModule`, the body is a single expression rather than an
+    `ScadObject` tree, so it is stored as the already-formatted scad
+    expression string.
+*/
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct ScadFunction {
+    name: String,
+    parameters: Vec<ScadParameter>,
+    expression: String,
+}
+
+impl ScadFunction {
+    pub fn new(name: &str, parameters: Vec<ScadParameter>, expression: &str) -> ScadFunction {
+        ScadFunction {
+            name: String::from(name),
+            parameters,
+            expression: String::from(expression),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+      Returns the scad code for the function definition, e.g. `function
+      foo(w, h) = w * h;`.
+    */
+    pub fn get_code(&self) -> String {
+        let params = self
+            .parameters
+            .iter()
+            .map(|param| param.get_code())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("function {}({}) = {};", self.name, params, self.expression)
+    }
+}
+
+#[cfg(test)]
+mod function_tests {
+    use super::*;
+
+    #[test]
+    fn function_test() {
+        let function = ScadFunction::new(
+            "foo",
+            vec![ScadParameter::new("w"), ScadParameter::new("h")],
+            "w * h",
+        );
+
+        assert_eq!(function.get_code(), "function foo(w, h) = w * h;");
+    }
+}
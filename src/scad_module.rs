@@ -0,0 +1,109 @@
+use crate::ScadObject;
+
+/**
+    A single named parameter of a `ScadModule`/`ScadFunction`, with an optional
+    default value.
+
+    ```SCAD
+    module foo(w, h=5) { ... }
+    //         ^w        ^h has a default
+    ```
+*/
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct ScadParameter {
+    name: String,
+    default: Option<String>,
+}
+
+impl ScadParameter {
+    pub fn new(name: &str) -> ScadParameter {
+        ScadParameter {
+            name: String::from(name),
+            default: None,
+        }
+    }
+
+    pub fn with_default(name: &str, default: &str) -> ScadParameter {
+        ScadParameter {
+            name: String::from(name),
+            default: Some(String::from(default)),
+        }
+    }
+
+    pub(crate) fn get_code(&self) -> String {
+        match &self.default {
+            Some(default) => format!("{}={}", self.name, default),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/**
+    A reusable scad `module`, made up of a name, a parameter list and a body.
+
+    Once added to a `ScadFile` (see `ScadFile::add_module`), the module can be
+    instantiated anywhere in the object tree using `ScadElement::Call`.
+*/
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct ScadModule {
+    name: String,
+    parameters: Vec<ScadParameter>,
+    body: ScadObject,
+}
+
+impl ScadModule {
+    pub fn new(name: &str, parameters: Vec<ScadParameter>, body: ScadObject) -> ScadModule {
+        ScadModule {
+            name: String::from(name),
+            parameters,
+            body,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+      Returns the scad code for the module definition, e.g. `module foo(w, h=5)
+      { ... }`.
+    */
+    pub fn get_code(&self) -> String {
+        let params = self
+            .parameters
+            .iter()
+            .map(ScadParameter::get_code)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("module {}({})\n{}", self.name, params, self.body.get_code())
+    }
+}
+
+#[cfg(test)]
+mod module_tests {
+    use super::*;
+    use crate::{na, ScadElement};
+
+    #[test]
+    fn module_test() {
+        let mut body = ScadObject::new(ScadElement::Union);
+        body.add_child(ScadObject::new(ScadElement::Cube(na::Vector3::new(
+            1.0, 1.0, 1.0,
+        ))));
+
+        let module = ScadModule::new(
+            "foo",
+            vec![
+                ScadParameter::new("w"),
+                ScadParameter::with_default("h", "5"),
+            ],
+            body,
+        );
+
+        assert_eq!(
+            module.get_code(),
+            "module foo(w, h=5)\nunion()\n{\n\tcube([1,1,1]);\n}"
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use crate::ScadElement;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
 
 /**
     An scad object which is a single scad element and can have zero or more child objects
@@ -50,6 +52,9 @@ pub struct ScadObject {
 
     // '%'
     transparent: bool,
+
+    // Special variables ($fa, $fs, $fn, ...) scoped to this object's children.
+    specials: BTreeMap<String, String>,
 }
 
 impl ScadObject {
@@ -61,6 +66,7 @@ impl ScadObject {
             important: false,
             highlight: false,
             transparent: false,
+            specials: BTreeMap::new(),
         }
     }
 
@@ -69,45 +75,75 @@ impl ScadObject {
     }
 
     /**
-      Returns the scad code for the object.
+      Scopes a special variable (e.g. `"fn"`, `"fa"`, `"t"`) to this object's
+      children by writing a `$name=value;` assignment at the top of its `{}`
+      block, overriding any file-wide value set via `ScadFile::set_special`.
+      The object's own element is not affected.
+      Takes ownership over the object and returns it, so it chains like
+      `important`/`highlight`/`transparent`.
+    */
+    pub fn with_special<S: Into<String>>(mut self, name: S, value: S) -> ScadObject {
+        self.specials.insert(name.into(), value.into());
+        self
+    }
 
-      If there are no children, only the code for the ScadElement of the
-      object followed by a `;` is returned. If children exist, the code for
-      the element is returned first, followed by the code for each child surrounded
-      by `{}` and indented 1 tab character.
+    /**
+      Returns the scad code for the object. Thin wrapper around `write_code`
+      for callers that just want a `String`.
     */
     pub fn get_code(&self) -> String {
-        let mut result: String;
-
-        //Get the code for the current element
-        result = self.element.clone().get_code();
+        let mut result = String::new();
+        // Writing to a String can never fail.
+        self.write_code(&mut result, 0)
+            .expect("formatting never fails");
+        result
+    }
 
+    /**
+      Writes the scad code for the object into `out`, walking the tree once
+      and tracking `indent` as it descends instead of materializing each
+      child's code and re-indenting it with string replacement.
+
+      If there are no children and no special variables scoped to this object,
+      only the code for the ScadElement of the object followed by a `;` is
+      written. Otherwise the code for the element is written first, followed
+      by any `with_special` assignments and the code for each child, all
+      surrounded by `{}` and indented 1 tab character per level.
+    */
+    pub fn write_code<W: Write>(&self, out: &mut W, indent: usize) -> fmt::Result {
         if self.important {
-            result = String::from("!") + &result;
+            out.write_char('!')?;
         } else if self.highlight {
-            result = String::from("#") + &result;
+            out.write_char('#')?;
         } else if self.transparent {
-            result = String::from("%") + &result;
+            out.write_char('%')?;
+        } else if self.disable {
+            out.write_char('*')?;
         }
 
-        //Adding the code for all children, or ; if none exist
-        result = result
-            + &(match self.children.len() {
-                0 => String::from(";"),
-                _ => {
-                    let mut child_code = String::from("\n{\n");
-                    for stmt in &self.children {
-                        //Add the children indented one line
-                        child_code = child_code + "\t" + &(stmt.get_code().replace("\n", "\n\t"));
-                        child_code += "\n";
-                    }
-
-                    //Add the final bracket and 'return' the result
-                    child_code + "}"
-                }
-            });
+        out.write_str(&self.element.clone().get_code())?;
 
-        result
+        if self.children.is_empty() && self.specials.is_empty() {
+            return out.write_char(';');
+        }
+
+        out.write_char('\n')?;
+        write_tabs(out, indent)?;
+        out.write_str("{\n")?;
+
+        for (name, value) in &self.specials {
+            write_tabs(out, indent + 1)?;
+            writeln!(out, "${}={};", name, value)?;
+        }
+
+        for child in &self.children {
+            write_tabs(out, indent + 1)?;
+            child.write_code(out, indent + 1)?;
+            out.write_char('\n')?;
+        }
+
+        write_tabs(out, indent)?;
+        out.write_char('}')
     }
 
     pub fn is_disabled(&self) -> bool {
@@ -187,6 +223,13 @@ impl ScadObject {
     }
 }
 
+fn write_tabs<W: Write>(out: &mut W, indent: usize) -> fmt::Result {
+    for _ in 0..indent {
+        out.write_char('\t')?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod statement_tests {
     use super::*;
@@ -228,4 +271,11 @@ mod statement_tests {
         let test_2 = ScadObject::new(ScadElement::Union).important();
         assert_eq!(test_2.get_code(), "!union();");
     }
+
+    #[test]
+    fn with_special_test() {
+        let test_stmt = ScadObject::new(ScadElement::Union).with_special("fn", "50");
+
+        assert_eq!(test_stmt.get_code(), "union()\n{\n\t$fn=50;\n}");
+    }
 }